@@ -0,0 +1,36 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// A startup problem that didn't stop the app from opening its window, so the
+/// home screen has something to show the user instead of a silent failure.
+#[derive(Clone, Serialize)]
+pub struct SetupError {
+    pub kind: String,
+    pub message: String,
+}
+
+impl SetupError {
+    pub fn new(kind: &str, message: impl Into<String>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Managed state collecting every recoverable `SetupError` seen during `setup`.
+#[derive(Default)]
+pub struct SetupErrors(Mutex<Vec<SetupError>>);
+
+impl SetupErrors {
+    pub fn push(&self, error: SetupError) {
+        eprintln!("[setup] {}: {}", error.kind, error.message);
+        self.0.lock().unwrap().push(error);
+    }
+}
+
+#[tauri::command]
+pub fn get_setup_errors(state: tauri::State<'_, SetupErrors>) -> Vec<SetupError> {
+    state.0.lock().unwrap().clone()
+}
@@ -1,3 +1,15 @@
+mod errors;
+mod search;
+mod settings;
+
+use errors::SetupError;
+
+#[cfg(not(debug_assertions))]
+use std::time::Duration;
+
+#[cfg(not(debug_assertions))]
+use tauri::AppHandle;
+use tauri::{Emitter, Manager};
 #[cfg(not(debug_assertions))]
 use tauri_plugin_shell::ShellExt;
 use tauri_plugin_sql::{Migration, MigrationKind};
@@ -8,6 +20,206 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Payload for `single-instance://launch`, forwarded from a second app launch so the
+/// frontend can react to things like a `nexuswork://task/<id>` deep link in `argv`.
+#[derive(Clone, serde::Serialize)]
+struct LaunchArgs {
+    argv: Vec<String>,
+    cwd: String,
+}
+
+#[cfg(not(debug_assertions))]
+const API_HEALTH_URL: &str = "http://127.0.0.1:2620/health";
+#[cfg(not(debug_assertions))]
+const API_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+#[cfg(not(debug_assertions))]
+const API_MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+#[cfg(not(debug_assertions))]
+const API_MAX_RESTART_ATTEMPTS: u32 = 10;
+/// How many health checks to let fail right after spawn without penalty, so a
+/// slow first boot (npm cold start, DB migrations) isn't mistaken for a hang.
+#[cfg(not(debug_assertions))]
+const API_STARTUP_GRACE_CHECKS: u32 = 3;
+/// Consecutive failed probes required before the watchdog kills the sidecar,
+/// so one transient blip doesn't trigger a full restart cycle.
+#[cfg(not(debug_assertions))]
+const API_CONSECUTIVE_FAILURES_TO_KILL: u32 = 2;
+
+/// Emitted on `api://status` so the frontend can show sidecar connection state.
+#[cfg(not(debug_assertions))]
+#[derive(Clone, serde::Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum ApiStatus {
+    Starting,
+    Ready,
+    Crashed { reason: String },
+    Restarting { attempt: u32, delay_secs: u64 },
+}
+
+#[cfg(not(debug_assertions))]
+fn emit_api_status(app: &AppHandle, status: ApiStatus) {
+    if let Err(err) = app.emit("api://status", status) {
+        eprintln!("[API] Failed to emit api://status: {}", err);
+    }
+}
+
+/// Spawns the `workany-api` sidecar and supervises it for the lifetime of the app:
+/// restarts on unexpected termination with capped exponential backoff, and polls
+/// `/health` in the background to force a restart if the process hangs without exiting.
+#[cfg(not(debug_assertions))]
+fn spawn_and_supervise_sidecar(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        use tauri_plugin_shell::process::CommandEvent;
+
+        // Shared (not just reset per-iteration) so the watchdog can clear it the
+        // moment the sidecar proves itself healthy again, instead of only the
+        // supervisor loop ever touching it.
+        let attempt = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        loop {
+            emit_api_status(&app, ApiStatus::Starting);
+
+            let sidecar_command = match app.shell().sidecar("workany-api") {
+                Ok(cmd) => cmd.env("PORT", "2620").env("NODE_ENV", "production"),
+                Err(err) => {
+                    let reason = format!("failed to prepare sidecar command: {err}");
+                    emit_api_status(&app, ApiStatus::Crashed { reason: reason.clone() });
+                    app.state::<errors::SetupErrors>().push(SetupError::new("sidecar", reason));
+                    return;
+                }
+            };
+
+            let (mut rx, child) = match sidecar_command.spawn() {
+                Ok(pair) => pair,
+                Err(err) => {
+                    let reason = format!("failed to spawn API sidecar: {err}");
+                    emit_api_status(&app, ApiStatus::Crashed { reason: reason.clone() });
+                    if !schedule_restart(&app, &attempt).await {
+                        app.state::<errors::SetupErrors>().push(SetupError::new("sidecar", reason));
+                        return;
+                    }
+                    continue;
+                }
+            };
+
+            let healthy = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let watchdog_app = app.clone();
+            let watchdog_healthy = healthy.clone();
+            let watchdog_attempt = attempt.clone();
+            let watchdog_pid = child.pid();
+            let mut watchdog_child = child;
+            let watchdog = tauri::async_runtime::spawn(async move {
+                let mut checks_since_spawn: u32 = 0;
+                let mut consecutive_failures: u32 = 0;
+                loop {
+                    tokio::time::sleep(API_HEALTH_CHECK_INTERVAL).await;
+                    checks_since_spawn += 1;
+                    match reqwest::get(API_HEALTH_URL).await {
+                        Ok(resp) if resp.status().is_success() => {
+                            consecutive_failures = 0;
+                            if !watchdog_healthy.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                                watchdog_attempt.store(0, std::sync::atomic::Ordering::SeqCst);
+                                emit_api_status(&watchdog_app, ApiStatus::Ready);
+                            }
+                        }
+                        _ => {
+                            let never_healthy =
+                                !watchdog_healthy.load(std::sync::atomic::Ordering::SeqCst);
+                            if checks_since_spawn <= API_STARTUP_GRACE_CHECKS && never_healthy {
+                                // Still within the startup grace period and the sidecar
+                                // hasn't come up yet at all: a slow first boot, not a hang.
+                                continue;
+                            }
+                            consecutive_failures += 1;
+                            if consecutive_failures < API_CONSECUTIVE_FAILURES_TO_KILL {
+                                continue;
+                            }
+                            // Sidecar is unresponsive; kill it so CommandEvent::Terminated
+                            // fires and the supervisor loop above restarts it.
+                            if let Some(pid) = watchdog_pid {
+                                eprintln!(
+                                    "[API] Health check failed {consecutive_failures} times in a row for pid {pid}, forcing restart"
+                                );
+                            }
+                            if let Err(err) = watchdog_child.kill() {
+                                eprintln!("[API] Failed to kill hung sidecar: {err}");
+                            }
+                            break;
+                        }
+                    }
+                }
+            });
+
+            let mut terminated_unexpectedly = true;
+            while let Some(event) = rx.recv().await {
+                match event {
+                    CommandEvent::Stdout(line) => {
+                        println!("[API] {}", String::from_utf8_lossy(&line));
+                    }
+                    CommandEvent::Stderr(line) => {
+                        eprintln!("[API Error] {}", String::from_utf8_lossy(&line));
+                    }
+                    CommandEvent::Error(error) => {
+                        eprintln!("[API Spawn Error] {}", error);
+                    }
+                    CommandEvent::Terminated(status) => {
+                        println!("[API] Process terminated with status: {:?}", status);
+                        terminated_unexpectedly = !healthy.load(std::sync::atomic::Ordering::SeqCst)
+                            || status.code() != Some(0);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            watchdog.abort();
+
+            if terminated_unexpectedly {
+                emit_api_status(
+                    &app,
+                    ApiStatus::Crashed {
+                        reason: "sidecar terminated unexpectedly".into(),
+                    },
+                );
+                if !schedule_restart(&app, &attempt).await {
+                    app.state::<errors::SetupErrors>().push(SetupError::new(
+                        "sidecar",
+                        "sidecar kept crashing and exceeded the restart attempt limit",
+                    ));
+                    return;
+                }
+            } else {
+                // Clean exit (e.g. app shutdown): stop supervising.
+                return;
+            }
+        }
+    });
+}
+
+/// Waits out the next exponential backoff window and emits `restarting`. Returns
+/// `false` once `API_MAX_RESTART_ATTEMPTS` is exceeded, telling the caller to give up.
+/// `attempt` only counts crashes since the sidecar last proved itself healthy —
+/// the watchdog resets it to 0 as soon as a health probe succeeds, so a handful
+/// of early transient crashes don't permanently use up the restart budget.
+#[cfg(not(debug_assertions))]
+async fn schedule_restart(app: &AppHandle, attempt: &std::sync::atomic::AtomicU32) -> bool {
+    let attempt = attempt.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    if attempt > API_MAX_RESTART_ATTEMPTS {
+        eprintln!("[API] Giving up after {attempt} failed restart attempts");
+        return false;
+    }
+    let delay = Duration::from_secs(2u64.saturating_pow(attempt)).min(API_MAX_RESTART_BACKOFF);
+    emit_api_status(
+        app,
+        ApiStatus::Restarting {
+            attempt,
+            delay_secs: delay.as_secs(),
+        },
+    );
+    tokio::time::sleep(delay).await;
+    true
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Database migrations
@@ -104,9 +316,54 @@ pub fn run() {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 6,
+            description: "create_messages_fts",
+            sql: r#"
+                CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+                    content,
+                    tool_output,
+                    tool_name,
+                    content='messages',
+                    content_rowid='id'
+                );
+
+                INSERT INTO messages_fts(rowid, content, tool_output, tool_name)
+                    SELECT id, content, tool_output, tool_name FROM messages;
+
+                CREATE TRIGGER IF NOT EXISTS messages_fts_ai AFTER INSERT ON messages BEGIN
+                    INSERT INTO messages_fts(rowid, content, tool_output, tool_name)
+                    VALUES (new.id, new.content, new.tool_output, new.tool_name);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS messages_fts_ad AFTER DELETE ON messages BEGIN
+                    INSERT INTO messages_fts(messages_fts, rowid, content, tool_output, tool_name)
+                    VALUES ('delete', old.id, old.content, old.tool_output, old.tool_name);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS messages_fts_au AFTER UPDATE ON messages BEGIN
+                    INSERT INTO messages_fts(messages_fts, rowid, content, tool_output, tool_name)
+                    VALUES ('delete', old.id, old.content, old.tool_output, old.tool_name);
+                    INSERT INTO messages_fts(rowid, content, tool_output, tool_name)
+                    VALUES (new.id, new.content, new.tool_output, new.tool_name);
+                END;
+            "#,
+            kind: MigrationKind::Up,
+        },
     ];
 
     tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            // A second launch raced in: focus the existing window instead of letting
+            // it spawn its own process and contend for sqlite:workany.db.
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            if let Err(err) = app.emit("single-instance://launch", LaunchArgs { argv, cwd }) {
+                eprintln!("[single-instance] Failed to emit launch event: {}", err);
+            }
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
@@ -116,39 +373,25 @@ pub fn run() {
                 .build(),
         )
         .setup(|app| {
+            app.manage(errors::SetupErrors::default());
+
+            let handle = app.handle().clone();
+            let state = match tauri::async_runtime::block_on(settings::AppState::try_load(&handle)) {
+                Ok(state) => state,
+                Err(message) => {
+                    app.state::<errors::SetupErrors>()
+                        .push(SetupError::new("database", message));
+                    settings::AppState::unavailable()
+                }
+            };
+            app.manage(state);
+
             // In development mode (tauri dev), skip sidecar and use external API server
             // Run `pnpm dev:api` separately for hot-reload support
             // In production, spawn the bundled API sidecar
             #[cfg(not(debug_assertions))]
             {
-                let sidecar_command = app.shell().sidecar("workany-api")
-                    .unwrap()
-                    .env("PORT", "2620")
-                    .env("NODE_ENV", "production");
-                let (mut _rx, mut _child) = sidecar_command.spawn().expect("Failed to spawn API sidecar");
-
-                // Log sidecar output
-                tauri::async_runtime::spawn(async move {
-                    use tauri_plugin_shell::process::CommandEvent;
-                    while let Some(event) = _rx.recv().await {
-                        match event {
-                            CommandEvent::Stdout(line) => {
-                                println!("[API] {}", String::from_utf8_lossy(&line));
-                            }
-                            CommandEvent::Stderr(line) => {
-                                eprintln!("[API Error] {}", String::from_utf8_lossy(&line));
-                            }
-                            CommandEvent::Error(error) => {
-                                eprintln!("[API Spawn Error] {}", error);
-                            }
-                            CommandEvent::Terminated(status) => {
-                                println!("[API] Process terminated with status: {:?}", status);
-                                break;
-                            }
-                            _ => {}
-                        }
-                    }
-                });
+                spawn_and_supervise_sidecar(app.handle().clone());
             }
 
             #[cfg(debug_assertions)]
@@ -160,7 +403,14 @@ pub fn run() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![greet])
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            settings::get_config,
+            settings::get_setting,
+            settings::set_setting,
+            errors::get_setup_errors,
+            search::search_messages,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
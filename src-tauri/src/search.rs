@@ -0,0 +1,58 @@
+use serde::Serialize;
+
+use crate::settings::AppState;
+
+/// One hit from `search_messages`: enough to let the frontend jump to the
+/// owning task and show why it matched.
+#[derive(Serialize, sqlx::FromRow)]
+pub struct MessageSearchResult {
+    pub task_id: String,
+    pub message_id: i64,
+    pub snippet: String,
+    pub created_at: String,
+}
+
+/// FTS5 query syntax (`"`, `*`, `AND`/`OR`/`NOT`, column filters) leaks through
+/// to users typing a plain search phrase. Unless `advanced` is set, wrap the
+/// whole query in double quotes so characters like `-` or `"` in a task prompt
+/// don't get interpreted as operators; escape embedded quotes FTS5-style.
+fn build_match_query(query: &str, advanced: bool) -> String {
+    if advanced {
+        return query.to_string();
+    }
+    format!("\"{}\"", query.replace('"', "\"\""))
+}
+
+#[tauri::command]
+pub async fn search_messages(
+    query: String,
+    limit: u32,
+    advanced: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<MessageSearchResult>, String> {
+    let pool = state
+        .pool()
+        .ok_or_else(|| "settings database is unavailable".to_string())?;
+
+    let match_query = build_match_query(&query, advanced.unwrap_or(false));
+
+    sqlx::query_as(
+        r#"
+        SELECT
+            messages.task_id AS task_id,
+            messages.id AS message_id,
+            snippet(messages_fts, -1, '[', ']', '…', 10) AS snippet,
+            messages.created_at AS created_at
+        FROM messages_fts
+        JOIN messages ON messages.id = messages_fts.rowid
+        WHERE messages_fts MATCH ?1
+        ORDER BY rank
+        LIMIT ?2
+        "#,
+    )
+    .bind(&match_query)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("search failed: {e}"))
+}
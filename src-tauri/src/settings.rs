@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Typed view over the `settings` key/value table. Known keys get a dedicated
+/// field so Rust call sites (sidecar env, port, API key) don't have to parse
+/// strings out of a map; anything else round-trips through `extra` untouched.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub api_port: Option<u16>,
+    pub api_key: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, String>,
+}
+
+impl AppConfig {
+    fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "api_port" => self.api_port.map(|v| v.to_string()),
+            "api_key" => self.api_key.clone(),
+            _ => self.extra.get(key).cloned(),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: String) {
+        match key {
+            "api_port" => self.api_port = value.parse().ok(),
+            "api_key" => self.api_key = Some(value),
+            _ => {
+                self.extra.insert(key.to_string(), value);
+            }
+        }
+    }
+}
+
+/// Managed app state: the parsed settings table, kept in memory so reads
+/// (`get_config`, `get_setting`) don't have to round-trip through SQLite.
+///
+/// `pool` is `None` when the settings database couldn't be opened at startup
+/// (see [`AppState::unavailable`], built from a [`AppState::try_load`] error
+/// in `setup`) — reads fall back to an empty `AppConfig` and writes fail with
+/// a clear error instead of panicking the whole app.
+pub struct AppState {
+    pub config: Mutex<AppConfig>,
+    pool: Option<SqlitePool>,
+}
+
+impl AppState {
+    /// An empty, read-only `AppState` used when the settings database couldn't
+    /// be opened at startup.
+    pub fn unavailable() -> Self {
+        Self {
+            config: Mutex::new(AppConfig::default()),
+            pool: None,
+        }
+    }
+
+    /// The shared `workany.db` pool, reused by other modules (e.g. `search`)
+    /// instead of opening a second connection pool.
+    pub fn pool(&self) -> Option<&SqlitePool> {
+        self.pool.as_ref()
+    }
+
+    /// Opens the same `workany.db` the `sql` plugin migrates, and loads every
+    /// row in `settings` into a typed `AppConfig`. Returns an error instead of
+    /// panicking so `setup` can record it as a `SetupError` and fall back to
+    /// [`AppState::unavailable`] rather than aborting the app.
+    pub async fn try_load(app: &AppHandle) -> Result<Self, String> {
+        let db_path = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("failed to resolve app data dir: {e}"))?
+            .join("workany.db");
+
+        let pool = SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+            .await
+            .map_err(|e| format!("failed to open settings database: {e}"))?;
+
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT key, value FROM settings")
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| format!("failed to load settings: {e}"))?;
+
+        let mut config = AppConfig::default();
+        for (key, value) in rows {
+            config.set(&key, value);
+        }
+
+        Ok(Self {
+            config: Mutex::new(config),
+            pool: Some(pool),
+        })
+    }
+}
+
+/// Payload for `settings://changed`, emitted after every successful `set_setting`.
+#[derive(Clone, Serialize)]
+struct SettingsChanged {
+    key: String,
+    value: String,
+}
+
+#[tauri::command]
+pub fn get_config(state: tauri::State<'_, AppState>) -> AppConfig {
+    state.config.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn get_setting(key: String, state: tauri::State<'_, AppState>) -> Option<String> {
+    state.config.lock().unwrap().get(&key)
+}
+
+#[tauri::command]
+pub async fn set_setting(
+    key: String,
+    value: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let pool = state
+        .pool
+        .as_ref()
+        .ok_or_else(|| "settings database is unavailable".to_string())?;
+
+    sqlx::query(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, datetime('now'))
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+    )
+    .bind(&key)
+    .bind(&value)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("failed to write setting: {e}"))?;
+
+    state.config.lock().unwrap().set(&key, value.clone());
+
+    app.emit("settings://changed", SettingsChanged { key, value })
+        .map_err(|e| format!("failed to emit settings://changed: {e}"))?;
+
+    Ok(())
+}